@@ -132,6 +132,8 @@ impl PatchSpec {
         let op_strides_times_input_storage_strides =
             zip(&self.strides, &input_layout_strides).map(|(a, b)| (*a as isize * b)).collect();
 
+        let separable_extreme = self.kernel_shape.iter().product::<usize>() > 1;
+
         Patch {
             spec: self,
             padded: pad_before.iter().any(|&p| p != 0) || pad_after.iter().any(|&p| p != 0),
@@ -144,6 +146,7 @@ impl PatchSpec {
             op_strides_times_input_storage_strides,
             valid_output_zone,
             invalid_output_zones,
+            separable_extreme,
         }
     }
 }
@@ -161,6 +164,13 @@ pub struct Patch {
     pub op_strides_times_input_storage_strides: TVec<isize>,
     pub valid_output_zone: TVec<Range<usize>>,
     pub invalid_output_zones: TVec<TVec<Range<usize>>>,
+    /// True when every axis of the kernel can be max/min-pooled independently, one
+    /// axis at a time, with a 1-D monotonic-deque sliding extreme (see
+    /// `sliding_window_extreme`) instead of the O(kernel_volume) per-window walk done
+    /// by `visit_all_*` + `at`. Rectangular kernels (the only shape `PatchSpec`
+    /// produces) are always separable this way, except the degenerate 1-element
+    /// kernel for which the fast path buys nothing.
+    pub separable_extreme: bool,
 }
 
 impl Patch {
@@ -299,6 +309,48 @@ impl Patch {
             .sum::<isize>();
         (center + self.standard_layout_data_field[patch_index]) as usize
     }
+
+    /// Max (or min) pooling over every output position described by this patch, in
+    /// O(input_volume) rather than O(output_volume * kernel_volume): `input` (laid
+    /// out row-major over `self.spec.input_shape`) is reduced one axis at a time
+    /// with `sliding_window_extreme`, each pass honoring that axis's own kernel
+    /// size, dilation, stride and padding. Since max/min pooling is separable,
+    /// chaining the per-axis passes in any order yields the same result as visiting
+    /// every output position through `Patch::at` and scanning its window.
+    ///
+    /// Only valid when `self.separable_extreme` is set; callers must fall back to
+    /// the `visit_all_*` + `Patch::at` walk otherwise (e.g. for the degenerate
+    /// single-element kernel, where the fast path buys nothing).
+    ///
+    /// This is the per-call kernel a `MaxPool`/`MinPool` op would reach for; wiring
+    /// it into an actual `Op`/`TypedOp` is outside this module's scope (no such
+    /// trait machinery exists in this tree yet).
+    pub fn pooled_extreme(&self, input: &[f32], max: bool) -> (Vec<f32>, TVec<usize>) {
+        assert!(self.separable_extreme, "pooled_extreme requires a separable kernel");
+        assert_eq!(input.len(), self.spec.input_shape.iter().product::<usize>());
+        let mut current = input.to_vec();
+        let mut shape: Vec<usize> = self.spec.input_shape.to_vec();
+        for axis in 0..self.rank() {
+            let (next, next_shape) = extreme_along_axis(
+                &current,
+                &shape,
+                axis,
+                self.spec.kernel_shape[axis],
+                self.spec.dilations[axis],
+                self.spec.strides[axis],
+                self.pad_before[axis],
+                self.pad_after[axis],
+                max,
+            );
+            current = next;
+            shape = next_shape;
+        }
+        // `extreme_along_axis` derives its own output length per axis independently
+        // of `PatchSpec::into_patch`'s `padding.compute()`; this check is the only
+        // thing keeping the two in sync, so it must hold in release builds too.
+        assert_eq!(shape, self.output_shape.to_vec());
+        (current, shape.into())
+    }
 }
 
 struct Window {
@@ -384,6 +436,424 @@ impl<'p> Iterator for SafePatchIterator<'p> {
     }
 }
 
+/// Rank support over a single level of a wavelet matrix: a stable partition of a
+/// range by one bit of each value, plus prefix counts of zero-bits so ranges can be
+/// remapped into the zero/one side in O(1).
+#[derive(Debug, Clone)]
+struct WaveletLevel {
+    // bits[i] is this level's bit of the i-th value, in the order left by the
+    // previous level's stable partition (the original order at the top level).
+    bits: Vec<bool>,
+    // rank0[i] = number of zero-bits in bits[0..i).
+    rank0: Vec<usize>,
+}
+
+impl WaveletLevel {
+    fn rank0(&self, i: usize) -> usize {
+        self.rank0[i]
+    }
+}
+
+/// A wavelet matrix over a fixed sequence of non-negative integers, supporting
+/// O(log maxval) range k-th-smallest queries. Used by the percentile pooling fast
+/// path to answer one query per output window of a contiguous (stride/dilation-free)
+/// input row.
+#[derive(Debug, Clone)]
+struct WaveletMatrix {
+    levels: Vec<WaveletLevel>,
+}
+
+impl WaveletMatrix {
+    /// `bits` is `ceil(log2(maxval + 1))`, i.e. enough levels to represent every
+    /// value in `values`.
+    fn build(values: &[u32], bits: u32) -> WaveletMatrix {
+        let mut order: Vec<u32> = values.to_vec();
+        let mut levels = Vec::with_capacity(bits as usize);
+        for level in (0..bits).rev() {
+            let bit = 1u32 << level;
+            let bits: Vec<bool> = order.iter().map(|&v| v & bit != 0).collect();
+            let mut rank0 = Vec::with_capacity(bits.len() + 1);
+            rank0.push(0);
+            for &b in &bits {
+                rank0.push(rank0.last().unwrap() + !b as usize);
+            }
+            // stable partition: all zero-bits first, in original relative order
+            let mut zeros: Vec<u32> = Vec::with_capacity(order.len());
+            let mut ones: Vec<u32> = Vec::with_capacity(order.len());
+            for (&v, &b) in order.iter().zip(bits.iter()) {
+                if b {
+                    ones.push(v)
+                } else {
+                    zeros.push(v)
+                }
+            }
+            zeros.extend(ones);
+            order = zeros;
+            levels.push(WaveletLevel { bits, rank0 });
+        }
+        WaveletMatrix { levels }
+    }
+
+    /// k-th smallest (0-indexed) value among the values originally at positions
+    /// `[l, r)`.
+    fn kth_smallest(&self, mut l: usize, mut r: usize, mut k: usize) -> u32 {
+        let mut answer = 0u32;
+        for (depth, level) in self.levels.iter().enumerate() {
+            let zeros_in_range = level.rank0(r) - level.rank0(l);
+            let bit_value = 1u32 << (self.levels.len() - 1 - depth);
+            if k < zeros_in_range {
+                l = level.rank0(l);
+                r = level.rank0(r);
+            } else {
+                answer |= bit_value;
+                k -= zeros_in_range;
+                let total_zeros = *level.rank0.last().unwrap();
+                l = total_zeros + (l - level.rank0(l));
+                r = total_zeros + (r - level.rank0(r));
+            }
+        }
+        answer
+    }
+}
+
+/// Values of a pooling window, gathered through a `PatchIterator`. Positions that
+/// fall outside the input (invalid / padded positions) are skipped rather than
+/// contributing a neutral value, matching `AvgPool`'s count-averaging semantics for
+/// edge windows.
+fn gather_window(patch: &Patch, coords: &[usize], input: &[f32]) -> TVec<f32> {
+    patch.at(coords).filter_map(|pos| pos.map(|pos| input[pos as usize])).collect()
+}
+
+/// The element at rank `floor(q * (n - 1))` of a pooling window centered at `coords`,
+/// or `None` if the window has no valid positions.
+///
+/// This is the general-purpose path: it collects the window through `Patch::at` and
+/// uses `select_nth_unstable_by` to find the requested rank in O(n). It is correct
+/// for any combination of strides and dilations, and is what non-contiguous windows
+/// fall back to; the contiguous, dilation-free case can instead be answered in
+/// O(log maxval) per window with a `WaveletMatrix` built once per input row (see
+/// `percentile_row_contiguous`).
+pub fn percentile_in_window(patch: &Patch, coords: &[usize], input: &[f32], q: f32) -> Option<f32> {
+    let mut window = gather_window(patch, coords, input);
+    if window.is_empty() {
+        return None;
+    }
+    let k = ((q * (window.len() - 1) as f32).floor() as usize).min(window.len() - 1);
+    let (_, &mut pivot, _) =
+        window.select_nth_unstable_by(k, |a, b| a.partial_cmp(b).unwrap());
+    Some(pivot)
+}
+
+/// Percentile pooling for a single contiguous (stride 1, dilation 1) row of `n`
+/// values and a kernel of length `kernel_shape`: answers the rank-`floor(q*(k-1))`
+/// query for every window in O(log n) via a wavelet matrix built once for the whole
+/// row, instead of the O(output * kernel) generic path.
+///
+/// `WaveletMatrix` only deals in dense non-negative integers, so the row is first
+/// coordinate-compressed into ranks `0..n` (stable on ties) and the matrix is built
+/// over those ranks; looked-up ranks are then mapped back to the original `f32`
+/// values through `sorted_values`. This keeps the fast path usable for arbitrary
+/// `f32` rows rather than only bounded integer ones.
+///
+/// Returns an empty vec if the kernel doesn't fit in the row at all.
+fn percentile_row_contiguous(input: &[f32], kernel_shape: usize, q: f32) -> Vec<f32> {
+    if kernel_shape == 0 || kernel_shape > input.len() {
+        return Vec::new();
+    }
+    let mut order: Vec<usize> = (0..input.len()).collect();
+    order.sort_by(|&a, &b| input[a].partial_cmp(&input[b]).unwrap());
+    let mut rank_of = vec![0u32; input.len()];
+    let mut sorted_values = vec![0f32; input.len()];
+    for (rank, &i) in order.iter().enumerate() {
+        rank_of[i] = rank as u32;
+        sorted_values[rank] = input[i];
+    }
+    let bits = (usize::BITS - (input.len() - 1).max(1).leading_zeros()).max(1);
+    let wavelet = WaveletMatrix::build(&rank_of, bits);
+    let k = ((q * (kernel_shape - 1) as f32).floor() as usize).min(kernel_shape - 1);
+    (0..=input.len() - kernel_shape)
+        .map(|start| sorted_values[wavelet.kth_smallest(start, start + kernel_shape, k) as usize])
+        .collect()
+}
+
+/// Percentile pooling over every output position described by `patch`, in row-major
+/// output order. Dispatches to the O(log n) wavelet fast path
+/// (`percentile_row_contiguous`) for the common 1-D, stride-1, dilation-1, unpadded
+/// case, building the wavelet matrix once for the whole row; every other
+/// combination of rank, strides, dilations or padding falls back to
+/// `percentile_in_window`, evaluated independently per output position.
+///
+/// `input` must be the flattened (row-major) spatial input described by
+/// `patch.spec.input_shape`, matching the convention `Patch::pooled_extreme` uses.
+pub fn percentile_pool(patch: &Patch, input: &[f32], q: f32) -> Vec<f32> {
+    assert_eq!(input.len(), patch.spec.input_shape.iter().product::<usize>());
+    let contiguous = patch.rank() == 1
+        && patch.spec.strides[0] == 1
+        && patch.spec.dilations[0] == 1
+        && !patch.padded;
+    if contiguous {
+        percentile_row_contiguous(input, patch.spec.kernel_shape[0], q)
+    } else {
+        ndarray::indices(&*patch.output_shape)
+            .into_iter()
+            .map(|coords| {
+                let coords: TVec<usize> = coords.slice().into();
+                percentile_in_window(patch, &coords, input, q).unwrap_or(0.0)
+            })
+            .collect()
+    }
+}
+
+/// Percentile (median by default) pooling kernel, built directly on `Patch`: the
+/// window geometry lives in `patch`, and `q` in `[0, 1]` selects the percentile
+/// (`0.5` is the median). Outside this module's tests, `eval` is the only caller of
+/// `percentile_pool` — this struct is a thin, directly-callable wrapper around it, not
+/// a full pooling operator. Making it one requires the `Op`/`TypedOp` trait impls
+/// (and the graph/type-inference machinery they hook into) that this source tree
+/// does not contain; until that lands, treat this as the computational core a real
+/// `PercentilePool` op would delegate to, not as that op itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercentilePool {
+    pub patch: Patch,
+    pub q: f32,
+}
+
+impl PercentilePool {
+    pub fn new(patch: Patch, q: f32) -> PercentilePool {
+        assert!((0.0..=1.0).contains(&q), "percentile must be in [0, 1], got {}", q);
+        PercentilePool { patch, q }
+    }
+
+    pub fn eval(&self, input: &[f32]) -> Vec<f32> {
+        percentile_pool(&self.patch, input, self.q)
+    }
+}
+
+/// One residue class of a dilated 1-D sliding extreme: the positions `residue,
+/// residue + dilation, residue + 2*dilation, ...` form an independent sub-sequence, so
+/// dilation `d` is handled by running this `d` times, once per starting residue.
+fn sliding_extreme_pass(
+    values: &[f32],
+    residue: usize,
+    dilation: usize,
+    kernel: usize,
+    keep_left: impl Fn(f32, f32) -> bool,
+    output: &mut [f32],
+) {
+    // indices into `values`, in this residue class, in increasing order
+    let indices: Vec<usize> = (residue..values.len()).step_by(dilation).collect();
+    let mut deque: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    for (pos, &i) in indices.iter().enumerate() {
+        while deque.back().map_or(false, |&back| !keep_left(values[back], values[i])) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        if pos + 1 >= kernel {
+            while *deque.front().unwrap() < indices[pos + 1 - kernel] {
+                deque.pop_front();
+            }
+            let out_index = indices[pos + 1 - kernel];
+            output[out_index] = values[*deque.front().unwrap()];
+        }
+    }
+}
+
+/// Sliding window max (or min, with `keep_left` flipped) along one axis of length
+/// `len`, for a kernel of `kernel` taps with the given `dilation`, keeping every
+/// `stride`-th window (so `strides == 1` matches every position, as `PatchSpec`
+/// already encodes per axis). `pad_before`/`pad_after` positions are treated as the
+/// identity (`f32::NEG_INFINITY` for max, `f32::INFINITY` for min) so they never win
+/// a window. Runs in O(len) total regardless of kernel size, versus O(len * kernel)
+/// for the naive per-window scan; this is the per-axis pass `Patch::pooled_extreme`
+/// chains together when `Patch::separable_extreme` is set.
+pub fn sliding_window_extreme(
+    axis: &[f32],
+    pad_before: usize,
+    pad_after: usize,
+    kernel: usize,
+    dilation: usize,
+    stride: usize,
+    max: bool,
+) -> Vec<f32> {
+    let identity = if max { f32::NEG_INFINITY } else { f32::INFINITY };
+    let padded: Vec<f32> = std::iter::repeat(identity)
+        .take(pad_before)
+        .chain(axis.iter().cloned())
+        .chain(std::iter::repeat(identity).take(pad_after))
+        .collect();
+    let keep_left = move |a: f32, b: f32| if max { a > b } else { a < b };
+    let mut output = vec![identity; padded.len()];
+    for residue in 0..dilation.min(padded.len().max(1)) {
+        sliding_extreme_pass(&padded, residue, dilation, kernel, keep_left, &mut output);
+    }
+    let out_len = padded.len() + 1 - (kernel - 1) * dilation - 1;
+    output[..out_len.min(output.len())].into_iter().step_by(stride).cloned().collect()
+}
+
+/// Row-major strides for a row-major array of the given `shape` (innermost axis
+/// varies fastest). Used by the sliding-extreme N-d driver.
+fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1usize; shape.len()];
+    for ix in (0..shape.len().saturating_sub(1)).rev() {
+        strides[ix] = strides[ix + 1] * shape[ix + 1];
+    }
+    strides
+}
+
+/// Runs `sliding_window_extreme` along one axis of an N-d row-major array, for
+/// every line parallel to that axis. Returns the pooled values and the new shape
+/// (that axis shrunk to the pooled length, every other axis unchanged).
+fn extreme_along_axis(
+    input: &[f32],
+    shape: &[usize],
+    axis: usize,
+    kernel: usize,
+    dilation: usize,
+    stride: usize,
+    pad_before: usize,
+    pad_after: usize,
+    max: bool,
+) -> (Vec<f32>, Vec<usize>) {
+    let strides = row_major_strides(shape);
+    let mut reduced_shape = shape.to_vec();
+    reduced_shape[axis] = 1;
+    // the pooled length is the same for every line along this axis, so it's only
+    // known once the first line has actually been pooled
+    let mut out_shape = shape.to_vec();
+    let mut flat = Vec::new();
+    let mut out_strides = Vec::new();
+    for coords in ndarray::indices(reduced_shape.clone()) {
+        let coords: Vec<usize> = coords.slice().to_vec();
+        let line_start: usize = coords.iter().zip(&strides).map(|(c, s)| c * s).sum();
+        let line: Vec<f32> =
+            (0..shape[axis]).map(|k| input[line_start + k * strides[axis]]).collect();
+        let pooled =
+            sliding_window_extreme(&line, pad_before, pad_after, kernel, dilation, stride, max);
+        if flat.is_empty() {
+            out_shape[axis] = pooled.len();
+            out_strides = row_major_strides(&out_shape);
+            flat = vec![0f32; out_shape.iter().product()];
+        }
+        // `coords[axis]` is always 0 (from `reduced_shape`), so this is exactly the
+        // line's start offset in the (possibly axis-resized) output array.
+        let out_line_start: usize = coords.iter().zip(&out_strides).map(|(c, s)| c * s).sum();
+        for (k, v) in pooled.into_iter().enumerate() {
+            flat[out_line_start + k * out_strides[axis]] = v;
+        }
+    }
+    (flat, out_shape)
+}
+
+/// An N-d inclusive prefix-sum (summed-area table) over a row-major array, built by
+/// an in-place running sum along every axis in turn: after axis `a` has been
+/// processed, `table[i]` holds the sum of all input elements whose coordinates are
+/// `<=` `i` on axes `0..=a` and unconstrained on the rest. Once every axis has been
+/// folded in, any axis-aligned window sum can be read off in O(2^rank) via
+/// `SummedAreaTable::sum`, independent of the window's size. Values are accumulated
+/// in `f64` to guard against overflow when the input holds large integer tensors.
+#[derive(Debug, Clone)]
+pub struct SummedAreaTable {
+    shape: TVec<usize>,
+    strides: TVec<usize>,
+    table: Vec<f64>,
+}
+
+impl SummedAreaTable {
+    /// `input` is a row-major array of the given `shape`.
+    pub fn build(input: &[f32], shape: &[usize]) -> SummedAreaTable {
+        let mut strides: TVec<usize> = tvec!(1; shape.len());
+        for ix in (0..shape.len().saturating_sub(1)).rev() {
+            strides[ix] = strides[ix + 1] * shape[ix + 1];
+        }
+        let mut table: Vec<f64> = input.iter().map(|&v| v as f64).collect();
+        for axis in 0..shape.len() {
+            let stride = strides[axis];
+            let mut reduced_shape: Vec<usize> = shape.to_vec();
+            reduced_shape[axis] = 1;
+            for coords in ndarray::indices(reduced_shape) {
+                let line_start: usize =
+                    coords.slice().iter().zip(&strides).map(|(c, s)| c * s).sum();
+                for step in 1..shape[axis] {
+                    let i = line_start + step * stride;
+                    table[i] += table[i - stride];
+                }
+            }
+        }
+        SummedAreaTable { shape: shape.into(), strides, table }
+    }
+
+    /// Sum of the hyper-rectangle `[lo, hi)`, by inclusion-exclusion of its `2^rank`
+    /// corners in the prefix-sum table. `lo`/`hi` must already be clamped to
+    /// `0..=shape`; an empty range on any axis sums to 0.
+    pub fn sum(&self, lo: &[usize], hi: &[usize]) -> f64 {
+        let rank = self.shape.len();
+        if (0..rank).any(|ix| lo[ix] >= hi[ix]) {
+            return 0.0;
+        }
+        let mut total = 0.0;
+        for mask in 0..(1usize << rank) {
+            let mut sign = 1.0;
+            let mut offset = 0usize;
+            let mut in_range = true;
+            for ix in 0..rank {
+                if mask & (1 << ix) != 0 {
+                    if lo[ix] == 0 {
+                        in_range = false;
+                        break;
+                    }
+                    sign = -sign;
+                    offset += (lo[ix] - 1) * self.strides[ix];
+                } else {
+                    offset += (hi[ix] - 1) * self.strides[ix];
+                }
+            }
+            if in_range {
+                total += sign * self.table[offset];
+            }
+        }
+        total
+    }
+}
+
+/// Average of the valid (non-padded) elements of the pooling window centered at
+/// `out_coords`, in O(1) via `sat` instead of re-summing every element of the
+/// window. Only covers dilation 1 (the summed-area table has no notion of dilation,
+/// since its ranges are always contiguous spans of the input); `sat` must have been
+/// built over the same (unpadded) input as `patch`. The window is clamped to the
+/// input bounds and divided by the clamped (valid) element count, matching the
+/// count-averaging semantics `AvgPool` already uses for the windows in
+/// `patch.invalid_output_zones`.
+///
+/// Panics if `patch` has any dilation other than 1; callers with dilated windows
+/// should fall back to summing `gather_window` directly instead of routing here.
+///
+/// This is the per-window averaging kernel an `AvgPool` op would call once per
+/// output position; wiring it into an actual `AvgPool` `Op`/`TypedOp` is outside
+/// this module's scope (no such trait machinery exists in this tree yet).
+pub fn window_average_from_sat(patch: &Patch, sat: &SummedAreaTable, out_coords: &[usize]) -> f32 {
+    assert!(
+        patch.spec.dilations.iter().all(|&d| d == 1),
+        "window_average_from_sat only supports dilation 1, got {:?}",
+        patch.spec.dilations
+    );
+    let rank = patch.rank();
+    let mut lo: TVec<usize> = tvec!(0; rank);
+    let mut hi: TVec<usize> = tvec!(0; rank);
+    let mut valid_count = 1usize;
+    for ix in 0..rank {
+        let start = out_coords[ix] * patch.spec.strides[ix];
+        let raw_lo = start as isize - patch.pad_before[ix] as isize;
+        let raw_hi = raw_lo + patch.spec.kernel_shape[ix] as isize;
+        lo[ix] = raw_lo.max(0) as usize;
+        hi[ix] = (raw_hi.max(0) as usize).min(patch.spec.input_shape[ix]).max(lo[ix]);
+        valid_count *= hi[ix] - lo[ix];
+    }
+    if valid_count == 0 {
+        return 0.0;
+    }
+    (sat.sum(&lo, &hi) / valid_count as f64) as f32
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -531,4 +1001,238 @@ pub mod test {
         }
         assert!(output.iter().all(|&x| x == 1));
     }
+
+    #[test]
+    fn wavelet_matrix_matches_sort() {
+        let values: Vec<u32> = vec![5, 1, 4, 2, 8, 3, 7, 6, 0];
+        let wavelet = WaveletMatrix::build(&values, 4);
+        for l in 0..values.len() {
+            for r in l + 1..=values.len() {
+                let mut sorted = values[l..r].to_vec();
+                sorted.sort_unstable();
+                for k in 0..sorted.len() {
+                    assert_eq!(wavelet.kth_smallest(l, r, k), sorted[k]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn percentile_row_contiguous_median_matches_generic() {
+        let row: Vec<f32> = vec![9.0, 2.0, 7.0, 4.0, 1.0, 8.0, 3.0, 6.0, 5.0];
+        let kernel = 3;
+        let fast = percentile_row_contiguous(&row, kernel, 0.5);
+        for (start, &got) in fast.iter().enumerate() {
+            let mut window: Vec<f32> = row[start..start + kernel].to_vec();
+            window.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(got, window[((0.5 * (kernel - 1) as f32).floor() as usize)]);
+        }
+    }
+
+    #[test]
+    fn percentile_row_contiguous_kernel_larger_than_row_is_empty() {
+        let row: Vec<f32> = vec![1.0, 2.0];
+        assert!(percentile_row_contiguous(&row, 3, 0.5).is_empty());
+    }
+
+    #[test]
+    fn percentile_pool_fast_path_matches_generic_path_for_contiguous_1d() {
+        // Same contiguous, stride-1, dilation-1, unpadded geometry that makes
+        // `percentile_pool` take the wavelet fast path; cross-check it against the
+        // generic `percentile_in_window` path instead of a second call into the same
+        // fast-path code.
+        let row: Vec<f32> = vec![9.0, 2.0, 7.0, 4.0, 1.0, 8.0, 3.0, 6.0, 5.0];
+        let patch = PatchSpec::for_full_shape(DataFormat::NCHW, &[1, 1, row.len()])
+            .with_kernel_shape(tvec![3])
+            .with_padding(PaddingSpec::Valid)
+            .with_strides(tvec![1])
+            .into_patch();
+        let pool = PercentilePool::new(patch.clone(), 0.5);
+        let got = pool.eval(&row);
+        let want: Vec<f32> = ndarray::indices(&*patch.output_shape)
+            .into_iter()
+            .map(|coords| {
+                let coords: TVec<usize> = coords.slice().into();
+                percentile_in_window(&patch, &coords, &row, 0.5).unwrap_or(0.0)
+            })
+            .collect();
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn percentile_pool_matches_generic_path_for_2d() {
+        // 2-D, stride-1 padded geometry never takes the fast path (`patch.rank() ==
+        // 1` fails), so `percentile_pool` runs `percentile_in_window` per output
+        // position here; this checks its dispatch and output order against direct
+        // per-window calls.
+        let input: Vec<f32> = (0..25).map(|i| i as f32).collect();
+        let patch = PatchSpec::for_full_shape(DataFormat::NCHW, &[1, 1, 5, 5])
+            .with_kernel_shape(tvec![3, 3])
+            .with_padding(PaddingSpec::SameLower)
+            .with_strides(tvec![1, 1])
+            .into_patch();
+        let got = percentile_pool(&patch, &input, 0.5);
+        let want: Vec<f32> = ndarray::indices(&*patch.output_shape)
+            .into_iter()
+            .map(|coords| {
+                let coords: TVec<usize> = coords.slice().into();
+                percentile_in_window(&patch, &coords, &input, 0.5).unwrap_or(0.0)
+            })
+            .collect();
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn percentile_in_window_skips_invalid_positions() {
+        let input: Vec<f32> = (0..25).map(|i| i as f32).collect();
+        let patch = PatchSpec::for_full_shape(DataFormat::NCHW, &[1, 1, 5, 5])
+            .with_kernel_shape(tvec![3, 3])
+            .with_padding(PaddingSpec::SameLower)
+            .with_strides(tvec![1, 1])
+            .into_patch();
+        // top-left output window: only the bottom-right 2x2 of the kernel is valid
+        let got = percentile_in_window(&patch, &[0, 0], &input, 0.5).unwrap();
+        let mut valid: Vec<f32> = vec![input[0], input[1], input[5], input[6]];
+        valid.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(got, valid[((0.5 * (valid.len() - 1) as f32).floor() as usize)]);
+    }
+
+    fn naive_sliding_max(
+        axis: &[f32],
+        pad_before: usize,
+        pad_after: usize,
+        kernel: usize,
+        dilation: usize,
+        stride: usize,
+    ) -> Vec<f32> {
+        let padded: Vec<f32> = std::iter::repeat(f32::NEG_INFINITY)
+            .take(pad_before)
+            .chain(axis.iter().cloned())
+            .chain(std::iter::repeat(f32::NEG_INFINITY).take(pad_after))
+            .collect();
+        let span = (kernel - 1) * dilation;
+        (0..=padded.len() - 1 - span)
+            .step_by(stride)
+            .map(|start| {
+                (0..kernel)
+                    .map(|k| padded[start + k * dilation])
+                    .fold(f32::NEG_INFINITY, f32::max)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sliding_window_extreme_matches_naive() {
+        let axis: Vec<f32> = vec![3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        for kernel in 1..4 {
+            for dilation in 1..3 {
+                for stride in 1..3 {
+                    let got = sliding_window_extreme(&axis, 0, 0, kernel, dilation, stride, true);
+                    let want = naive_sliding_max(&axis, 0, 0, kernel, dilation, stride);
+                    assert_eq!(got, want, "kernel {} dilation {} stride {}", kernel, dilation, stride);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sliding_window_extreme_honors_padding() {
+        let axis: Vec<f32> = vec![1.0, 2.0, 3.0];
+        let got = sliding_window_extreme(&axis, 1, 1, 3, 1, 1, true);
+        let want = naive_sliding_max(&axis, 1, 1, 3, 1, 1);
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn sliding_window_extreme_min() {
+        let axis: Vec<f32> = vec![3.0, 1.0, 4.0, 1.0, 5.0];
+        let got = sliding_window_extreme(&axis, 0, 0, 2, 1, 1, false);
+        assert_eq!(got, vec![1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn sliding_window_extreme_strided_subsamples_output() {
+        let axis: Vec<f32> = vec![3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let got = sliding_window_extreme(&axis, 0, 0, 2, 1, 2, true);
+        let want = naive_sliding_max(&axis, 0, 0, 2, 1, 2);
+        assert_eq!(got, want);
+    }
+
+    proptest! {
+        #[test]
+        fn pooled_extreme_matches_visit_all_when_separable((_, p) in patch_2d(), max in any::<bool>()) {
+            prop_assume!(p.separable_extreme);
+            let volume: usize = p.spec.input_shape.iter().product();
+            let input: Vec<f32> = (0..volume).map(|i| ((i * 2654435761) % 97) as f32).collect();
+            let (got, shape) = p.pooled_extreme(&input, max);
+            prop_assert_eq!(&*shape, &*p.output_shape);
+            let identity = if max { f32::NEG_INFINITY } else { f32::INFINITY };
+            let fold = if max { f32::max } else { f32::min };
+            for (coords, _) in p.visit_all_2() {
+                let want =
+                    gather_window(&p, &[coords.0, coords.1], &input).into_iter().fold(identity, fold);
+                let idx = coords.0 * shape[1] + coords.1;
+                prop_assert_eq!(got[idx], want, "coords {:?} max {}", coords, max);
+            }
+        }
+    }
+
+    fn naive_sum_2d(input: &[f32], shape: &[usize], lo: &[usize], hi: &[usize]) -> f64 {
+        let mut total = 0.0;
+        for i in lo[0]..hi[0] {
+            for j in lo[1]..hi[1] {
+                total += input[i * shape[1] + j] as f64;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn summed_area_table_matches_naive_sum() {
+        let shape = [4usize, 5usize];
+        let input: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        let sat = SummedAreaTable::build(&input, &shape);
+        for lo0 in 0..shape[0] {
+            for hi0 in lo0 + 1..=shape[0] {
+                for lo1 in 0..shape[1] {
+                    for hi1 in lo1 + 1..=shape[1] {
+                        let want = naive_sum_2d(&input, &shape, &[lo0, lo1], &[hi0, hi1]);
+                        let got = sat.sum(&[lo0, lo1], &[hi0, hi1]);
+                        assert_eq!(got, want, "lo {:?} hi {:?}", (lo0, lo1), (hi0, hi1));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn window_average_from_sat_matches_gather_window_average() {
+        let input: Vec<f32> = (0..25).map(|i| i as f32).collect();
+        let patch = PatchSpec::for_full_shape(DataFormat::NCHW, &[1, 1, 5, 5])
+            .with_kernel_shape(tvec![3, 3])
+            .with_padding(PaddingSpec::SameLower)
+            .with_strides(tvec![1, 1])
+            .into_patch();
+        let sat = SummedAreaTable::build(&input, &[5, 5]);
+        for (coords, _) in patch.visit_all_2() {
+            let want_window = gather_window(&patch, &[coords.0, coords.1], &input);
+            let want = want_window.iter().sum::<f32>() / want_window.len() as f32;
+            let got = window_average_from_sat(&patch, &sat, &[coords.0, coords.1]);
+            assert!((got - want).abs() < 1e-4, "coords {:?}: got {} want {}", coords, got, want);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "only supports dilation 1")]
+    fn window_average_from_sat_rejects_dilation() {
+        let input: Vec<f32> = (0..25).map(|i| i as f32).collect();
+        let patch = PatchSpec::for_full_shape(DataFormat::NCHW, &[1, 1, 5, 5])
+            .with_kernel_shape(tvec![3, 3])
+            .with_padding(PaddingSpec::SameLower)
+            .with_strides(tvec![1, 1])
+            .with_dilations(tvec![2, 1])
+            .into_patch();
+        let sat = SummedAreaTable::build(&input, &[5, 5]);
+        window_average_from_sat(&patch, &sat, &[0, 0]);
+    }
 }
\ No newline at end of file